@@ -0,0 +1,417 @@
+use std::collections::HashSet;
+
+use schemars::Schema;
+use serde::de::Error as _;
+use serde_json::{Map, Value, json};
+
+use super::generator::TryGet;
+use super::naming::NameAllocator;
+
+/// Converts a [`Schema`] produced by [`super::SchemaGenerator`] into an
+/// Apache Avro schema document, so inferred shapes can be used directly as
+/// Avro writer/reader schemas.
+pub struct AvroExporter<'defs> {
+    root_name: String,
+    definitions: &'defs dyn TryGet<String, Schema>,
+}
+
+impl<'defs> AvroExporter<'defs> {
+    /// `definitions` resolves `$ref`s against a `definitions`/`$defs` map,
+    /// the same way [`super::Validator`] does — pass the
+    /// `SchemaGenerator::dedupe_subschemas` output's definitions store here
+    /// when exporting a deduplicated schema.
+    pub fn new(
+        root_name: impl Into<String>,
+        definitions: &'defs dyn TryGet<String, Schema>,
+    ) -> Self {
+        Self { root_name: root_name.into(), definitions }
+    }
+
+    /// Exports `schema` as an Avro schema document.
+    pub fn export(&self, schema: &Schema) -> Result<Value, serde_json::Error> {
+        let mut names = NameAllocator::default();
+        self.convert(schema, &self.root_name, &mut names)
+    }
+
+    fn convert(
+        &self,
+        schema: &Schema,
+        name_hint: &str,
+        names: &mut NameAllocator,
+    ) -> Result<Value, serde_json::Error> {
+        let Value::Object(obj) = schema.as_value() else {
+            return Ok(json!("null"));
+        };
+
+        if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+            return match self.definitions.try_get(&reference.to_string()) {
+                Some(resolved) => self.convert(&resolved, name_hint, names),
+                None => Err(serde_json::Error::custom(format!(
+                    "unresolved $ref '{reference}'"
+                ))),
+            };
+        }
+
+        if let Some(branches) = obj.get("oneOf").and_then(Value::as_array) {
+            return self.convert_one_of(branches, name_hint, names);
+        }
+
+        match obj.get("type").and_then(Value::as_str) {
+            Some("object") => self.convert_record(obj, name_hint, names),
+            Some("array") => self.convert_array(obj, name_hint, names),
+            Some("integer") => Ok(json!("long")),
+            Some("number") => Ok(json!("double")),
+            Some("string") => Ok(json!("string")),
+            Some("boolean") => Ok(json!("boolean")),
+            _ => Ok(json!("null")),
+        }
+    }
+
+    fn convert_record(
+        &self,
+        obj: &Map<String, Value>,
+        name_hint: &str,
+        names: &mut NameAllocator,
+    ) -> Result<Value, serde_json::Error> {
+        let record_name = names.allocate(name_hint, "Record");
+
+        let required: HashSet<&str> = obj
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|keys| keys.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut fields = Vec::new();
+        if let Some(properties) =
+            obj.get("properties").and_then(Value::as_object)
+        {
+            for (key, sub_schema) in properties {
+                let sub_schema = Schema::try_from(sub_schema.clone())?;
+                let field_type = self.convert(&sub_schema, key, names)?;
+
+                let field = if required.contains(key.as_str()) {
+                    json!({"name": key, "type": field_type})
+                } else {
+                    json!({
+                        "name": key,
+                        "type": ["null", field_type],
+                        "default": Value::Null
+                    })
+                };
+                fields.push(field);
+            }
+        }
+
+        // Map iteration order isn't guaranteed; sort fields by name so the
+        // emitted document is deterministic.
+        fields.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+        Ok(json!({
+            "type": "record",
+            "name": record_name,
+            "fields": fields
+        }))
+    }
+
+    fn convert_array(
+        &self,
+        obj: &Map<String, Value>,
+        name_hint: &str,
+        names: &mut NameAllocator,
+    ) -> Result<Value, serde_json::Error> {
+        // Avro has no tuple type; a prefixItems tuple (2020-12) or a
+        // draft-07 array-valued `items` becomes an array whose items are
+        // the union of the distinct per-position types.
+        let tuple_items = obj
+            .get("prefixItems")
+            .and_then(Value::as_array)
+            .or_else(|| obj.get("items").and_then(Value::as_array));
+        if let Some(tuple_items) = tuple_items {
+            let item_type = self.convert_one_of(tuple_items, name_hint, names)?;
+            return Ok(json!({"type": "array", "items": item_type}));
+        }
+
+        let item_schema = obj.get("items").cloned().unwrap_or(json!({}));
+        let item_schema = Schema::try_from(item_schema)?;
+        let item_type = self.convert(&item_schema, name_hint, names)?;
+
+        Ok(json!({"type": "array", "items": item_type}))
+    }
+
+    /// Flattens nested `oneOf`s and deduplicates branch types, since Avro
+    /// unions must be a single flat list of distinct types.
+    fn convert_one_of(
+        &self,
+        branches: &[Value],
+        name_hint: &str,
+        names: &mut NameAllocator,
+    ) -> Result<Value, serde_json::Error> {
+        let mut flattened = Vec::new();
+        self.flatten_one_of(branches, name_hint, names, &mut flattened)?;
+
+        let mut deduped = Vec::new();
+        for branch_type in flattened {
+            if !deduped.contains(&branch_type) {
+                deduped.push(branch_type);
+            }
+        }
+
+        Ok(Value::Array(deduped))
+    }
+
+    fn flatten_one_of(
+        &self,
+        branches: &[Value],
+        name_hint: &str,
+        names: &mut NameAllocator,
+        out: &mut Vec<Value>,
+    ) -> Result<(), serde_json::Error> {
+        for branch in branches {
+            if let Some(nested) = branch.get("oneOf").and_then(Value::as_array)
+            {
+                self.flatten_one_of(nested, name_hint, names, out)?;
+                continue;
+            }
+
+            let branch_schema = Schema::try_from(branch.clone())?;
+            out.push(self.convert(&branch_schema, name_hint, names)?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::json_schema;
+    use std::collections::HashMap;
+
+    struct MockDefinitions {
+        refs: HashMap<String, Schema>,
+    }
+
+    impl TryGet<String, Schema> for MockDefinitions {
+        fn try_get(&self, key: &String) -> Option<Schema> {
+            self.refs.get(key).cloned()
+        }
+    }
+
+    fn no_refs() -> MockDefinitions {
+        MockDefinitions { refs: HashMap::new() }
+    }
+
+    #[test]
+    fn test_export_scalar_types() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        assert_eq!(
+            exporter.export(&json_schema!({"type": "integer"})).unwrap(),
+            json!("long")
+        );
+        assert_eq!(
+            exporter.export(&json_schema!({"type": "number"})).unwrap(),
+            json!("double")
+        );
+        assert_eq!(
+            exporter.export(&json_schema!({"type": "string"})).unwrap(),
+            json!("string")
+        );
+        assert_eq!(
+            exporter.export(&json_schema!({"type": "boolean"})).unwrap(),
+            json!("boolean")
+        );
+        assert_eq!(
+            exporter.export(&json_schema!({"type": "null"})).unwrap(),
+            json!("null")
+        );
+    }
+
+    #[test]
+    fn test_export_record_with_required_and_optional_fields() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
+            "required": ["name"]
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "type": "record",
+                "name": "Root",
+                "fields": [
+                    {
+                        "name": "age",
+                        "type": ["null", "long"],
+                        "default": null
+                    },
+                    {"name": "name", "type": "string"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_array_of_records() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {"id": {"type": "integer"}},
+                "required": ["id"]
+            }
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Root",
+                    "fields": [{"name": "id", "type": "long"}]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_one_of_flattens_and_dedups() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "oneOf": [
+                {"oneOf": [{"type": "integer"}, {"type": "string"}]},
+                {"type": "integer"}
+            ]
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(result, json!(["long", "string"]));
+    }
+
+    #[test]
+    fn test_export_prefix_items_stays_an_array() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "integer"}, {"type": "string"}],
+            "items": false
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(
+            result,
+            json!({"type": "array", "items": ["long", "string"]})
+        );
+    }
+
+    #[test]
+    fn test_export_draft07_items_array_stays_an_array() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "array",
+            "items": [{"type": "integer"}, {"type": "string"}],
+            "additionalItems": false
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(
+            result,
+            json!({"type": "array", "items": ["long", "string"]})
+        );
+    }
+
+    #[test]
+    fn test_export_optional_prefix_items_field_is_not_nested_union() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "point": {
+                    "type": "array",
+                    "prefixItems": [{"type": "integer"}, {"type": "string"}],
+                    "items": false
+                }
+            },
+            "required": []
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(
+            result["fields"][0],
+            json!({
+                "name": "point",
+                "type": ["null", {"type": "array", "items": ["long", "string"]}],
+                "default": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_names_nested_record_after_property_key() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"line": {"type": "string"}},
+                        "required": ["line"]
+                    }
+                }
+            },
+            "required": ["address"]
+        });
+        let result = exporter.export(&schema).unwrap();
+        let field_type = &result["fields"][0]["type"]["items"];
+        assert_eq!(field_type["name"], json!("Address"));
+    }
+
+    #[test]
+    fn test_export_resolves_ref() {
+        let defs = MockDefinitions {
+            refs: HashMap::from([(
+                "#/$defs/Address".to_string(),
+                json_schema!({
+                    "type": "object",
+                    "properties": {"line": {"type": "string"}},
+                    "required": ["line"]
+                }),
+            )]),
+        };
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "home": {"$ref": "#/$defs/Address"},
+                "work": {"$ref": "#/$defs/Address"}
+            },
+            "required": ["home", "work"]
+        });
+        let result = exporter.export(&schema).unwrap();
+        assert_eq!(result["fields"][0]["name"], json!("home"));
+        assert_eq!(result["fields"][0]["type"]["type"], json!("record"));
+        assert_eq!(
+            result["fields"][0]["type"]["fields"][0]["name"],
+            json!("line")
+        );
+    }
+
+    #[test]
+    fn test_export_unresolved_ref_errors() {
+        let defs = no_refs();
+        let exporter = AvroExporter::new("Root", &defs);
+        let schema = json_schema!({"$ref": "#/$defs/Missing"});
+        assert!(exporter.export(&schema).is_err());
+    }
+}
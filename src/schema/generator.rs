@@ -1,15 +1,130 @@
+use std::collections::HashMap;
+
 use schemars::{Schema, json_schema};
 use serde_json::{Map, Value, json};
 
+use super::naming::NameAllocator;
+use super::settings::SchemaSettings;
+
 pub trait TryGet<K, V> {
     fn try_get(&self, key: &K) -> Option<V>;
 }
 
 pub struct SchemaGenerator<'store> {
+    settings: SchemaSettings,
     known_types: &'store dyn TryGet<Value, Schema>,
 }
 
 impl<'store> SchemaGenerator<'store> {
+    pub fn new(
+        settings: SchemaSettings,
+        known_types: &'store dyn TryGet<Value, Schema>,
+    ) -> Self {
+        Self {
+            settings,
+            known_types,
+        }
+    }
+
+    /// A separate post-processing pass you run on a schema
+    /// [`generate_schema`](Self::generate_schema) already produced — it is
+    /// not invoked by `generate_schema` itself, since deduplication needs
+    /// the whole generated document to find repeated shapes. Hoists object
+    /// shapes that occur more than once in `schema` into a shared
+    /// definitions map (at `SchemaSettings::definitions_path`) and replaces
+    /// the inlined duplicates with `$ref`s. A no-op when
+    /// `SchemaSettings::inline_subschemas` is `true` (the default).
+    ///
+    /// [`generate_object_schema`](Self::generate_object_schema) copies an
+    /// instance's `$ref` key into the generated schema unchanged and looks
+    /// up only whole instances in `known_types`; it does not resolve the
+    /// `$ref`s this pass emits.
+    pub fn dedupe_subschemas(
+        &self,
+        schema: Schema,
+    ) -> Result<Schema, serde_json::Error> {
+        if self.settings.inline_subschemas {
+            return Ok(schema);
+        }
+
+        let mut root = schema.to_value();
+
+        let mut shapes: HashMap<Value, (String, usize)> = HashMap::new();
+        collect_object_shapes(&root, "Root", &mut shapes);
+
+        let mut candidates: Vec<(&Value, &str)> = shapes
+            .iter()
+            .filter(|(_, (_, count))| *count > 1)
+            .map(|(shape, (hint, _))| (shape, hint.as_str()))
+            .collect();
+        // HashMap iteration order isn't stable; sort so repeated runs over
+        // the same instance assign the same names.
+        candidates.sort_by(|a, b| {
+            a.1.cmp(b.1).then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+
+        let mut names = NameAllocator::default();
+        let mut assigned: HashMap<Value, String> = HashMap::new();
+        let mut defs: Vec<(String, Value)> = Vec::new();
+        for (shape, hint) in candidates {
+            let name = names.allocate(hint, "Def");
+            assigned.insert(shape.clone(), name.clone());
+            defs.push((name, shape.clone()));
+        }
+
+        if assigned.is_empty() {
+            return Schema::try_from(root);
+        }
+
+        if let Value::Object(obj) = &mut root {
+            if let Some(properties) =
+                obj.get_mut("properties").and_then(Value::as_object_mut)
+            {
+                for sub_value in properties.values_mut() {
+                    replace_with_refs(
+                        sub_value,
+                        &assigned,
+                        &self.settings.definitions_path,
+                    );
+                }
+            }
+            if let Some(items) = obj.get_mut("items") {
+                match items {
+                    Value::Object(_) => replace_with_refs(
+                        items,
+                        &assigned,
+                        &self.settings.definitions_path,
+                    ),
+                    Value::Array(elements) => {
+                        for element in elements {
+                            replace_with_refs(
+                                element,
+                                &assigned,
+                                &self.settings.definitions_path,
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(prefix_items) =
+                obj.get_mut("prefixItems").and_then(Value::as_array_mut)
+            {
+                for element in prefix_items {
+                    replace_with_refs(
+                        element,
+                        &assigned,
+                        &self.settings.definitions_path,
+                    );
+                }
+            }
+
+            insert_definitions(obj, &self.settings.definitions_path, defs);
+        }
+
+        Schema::try_from(root)
+    }
+
     pub fn generate_schema(
         &self,
         instance: &Value,
@@ -17,7 +132,19 @@ impl<'store> SchemaGenerator<'store> {
         match instance {
             Value::Object(_) => self.generate_object_schema(instance),
             Value::Array(arr) => self.generate_array_schema(arr),
-            Value::String(_) => Ok(json_schema!({"type": "string"})),
+            Value::String(s) => {
+                let format = self
+                    .settings
+                    .infer_string_formats
+                    .then(|| infer_string_format(s))
+                    .flatten();
+                match format {
+                    Some(format) => {
+                        Schema::try_from(json!({"type": "string", "format": format}))
+                    }
+                    None => Ok(json_schema!({"type": "string"})),
+                }
+            }
             Value::Number(n) => {
                 if n.is_i64() {
                     Ok(json_schema!({"type": "integer"}))
@@ -71,8 +198,9 @@ impl<'store> SchemaGenerator<'store> {
             }
 
             // Add $schema only to the top-level object
-            schema["$schema"] =
-                json!("http://json-schema.org/draft-07/schema#");
+            if let Some(meta_schema) = &self.settings.meta_schema {
+                schema["$schema"] = json!(meta_schema);
+            }
 
             Schema::try_from(schema)
         }
@@ -95,6 +223,10 @@ impl<'store> SchemaGenerator<'store> {
             item_schemas.push(self.generate_schema(item)?)
         }
 
+        if self.settings.tuple_arrays && looks_like_tuple(&item_schemas) {
+            return self.generate_tuple_schema(item_schemas);
+        }
+
         let common_schema = self.find_common_schema(&item_schemas)?;
 
         Ok(json_schema!({
@@ -103,6 +235,40 @@ impl<'store> SchemaGenerator<'store> {
         }))
     }
 
+    /// Emit a positional tuple schema: `prefixItems`/`items: false` in
+    /// 2020-12 mode, or the draft-07 `items` array + `additionalItems:
+    /// false` form otherwise.
+    fn generate_tuple_schema(
+        &self,
+        item_schemas: Vec<Schema>,
+    ) -> Result<Schema, serde_json::Error> {
+        let prefix: Vec<Value> =
+            item_schemas.into_iter().map(|s| s.to_value()).collect();
+
+        let schema = if self.uses_2020_12_dialect() {
+            json!({
+                "type": "array",
+                "prefixItems": prefix,
+                "items": false
+            })
+        } else {
+            json!({
+                "type": "array",
+                "items": prefix,
+                "additionalItems": false
+            })
+        };
+
+        Schema::try_from(schema)
+    }
+
+    fn uses_2020_12_dialect(&self) -> bool {
+        self.settings
+            .meta_schema
+            .as_deref()
+            .is_some_and(|s| s.contains("2020-12"))
+    }
+
     fn find_common_schema(
         &self,
         schemas: &[Schema],
@@ -127,6 +293,18 @@ impl<'store> SchemaGenerator<'store> {
             return Ok(schema1.clone());
         }
 
+        if let Some(merged) = self.merge_nullable(schema1, schema2)? {
+            return Ok(merged);
+        }
+
+        if let Some(merged) = self.merge_tuple_arrays(schema1, schema2)? {
+            return Ok(merged);
+        }
+
+        if let Some(merged) = merge_strings(schema1, schema2) {
+            return Ok(merged);
+        }
+
         let mut merged = json!({
             "oneOf": [schema1, schema2]
         });
@@ -156,6 +334,468 @@ impl<'store> SchemaGenerator<'store> {
 
         Schema::try_from(merged)
     }
+
+    /// If exactly one of `schema1`/`schema2` is the bare `{"type": "null"}`
+    /// schema, fold it into the other according to the configured
+    /// nullability style instead of falling through to `oneOf`. Returns
+    /// `None` when neither side is a null schema, in which case the caller
+    /// should use the normal merge path.
+    fn merge_nullable(
+        &self,
+        schema1: &Schema,
+        schema2: &Schema,
+    ) -> Result<Option<Schema>, serde_json::Error> {
+        let (null_schema, other) = match (is_null_schema(schema1), is_null_schema(schema2)) {
+            (true, false) => (schema1, schema2),
+            (false, true) => (schema2, schema1),
+            _ => return Ok(None),
+        };
+        let _ = null_schema;
+
+        if self.settings.nullable_as_keyword {
+            let mut merged = other.as_value().clone();
+            if let Some(obj) = merged.as_object_mut() {
+                obj.insert("nullable".to_string(), Value::Bool(true));
+            }
+            return Ok(Some(Schema::try_from(merged)?));
+        }
+
+        if self.settings.option_add_null_type
+            && let Value::Object(obj) = other.as_value()
+            && let Some(ty) = obj.get("type")
+        {
+            let mut types: Vec<Value> = match ty {
+                Value::Array(types) => types.clone(),
+                scalar => vec![scalar.clone()],
+            };
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+
+            let mut merged = other.as_value().clone();
+            if let Some(merged_obj) = merged.as_object_mut() {
+                merged_obj.insert("type".to_string(), Value::Array(types));
+            }
+            return Ok(Some(Schema::try_from(merged)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Merge two array schemas that were each generated in tuple form
+    /// (`prefixItems`, or draft-07's array-valued `items`). Same-length
+    /// tuples merge position by position; a length mismatch means two
+    /// instances of the same array key disagree on shape, so we fall back
+    /// to collapsing every observed element into one common `items`
+    /// schema.
+    fn merge_tuple_arrays(
+        &self,
+        schema1: &Schema,
+        schema2: &Schema,
+    ) -> Result<Option<Schema>, serde_json::Error> {
+        let (Value::Object(obj1), Value::Object(obj2)) =
+            (schema1.as_value(), schema2.as_value())
+        else {
+            return Ok(None);
+        };
+        if obj1.get("type") != Some(&json!("array"))
+            || obj2.get("type") != Some(&json!("array"))
+        {
+            return Ok(None);
+        }
+
+        let positions = |obj: &Map<String, Value>| {
+            obj.get("prefixItems")
+                .or_else(|| obj.get("items"))
+                .and_then(Value::as_array)
+                .cloned()
+        };
+        let (Some(positions1), Some(positions2)) =
+            (positions(obj1), positions(obj2))
+        else {
+            return Ok(None);
+        };
+
+        if positions1.len() == positions2.len() {
+            let mut merged_positions = Vec::new();
+            for (a, b) in positions1.iter().zip(positions2.iter()) {
+                let merged = self.merge_schemas(
+                    &Schema::try_from(a.clone())?,
+                    &Schema::try_from(b.clone())?,
+                )?;
+                merged_positions.push(merged.to_value());
+            }
+            return Ok(Some(self.generate_tuple_schema(
+                merged_positions
+                    .into_iter()
+                    .map(|v| Schema::try_from(v).unwrap())
+                    .collect(),
+            )?));
+        }
+
+        let mut all = Vec::new();
+        for value in positions1.into_iter().chain(positions2) {
+            all.push(Schema::try_from(value)?);
+        }
+        let common = self.find_common_schema(&all)?;
+        Ok(Some(json_schema!({
+            "type": "array",
+            "items": common
+        })))
+    }
+}
+
+/// True for the exact schema `{"type": "null"}`, which is what
+/// [`SchemaGenerator::generate_schema`] produces for [`Value::Null`].
+fn is_null_schema(schema: &Schema) -> bool {
+    matches!(
+        schema.as_value(),
+        Value::Object(obj)
+            if obj.len() == 1 && obj.get("type") == Some(&json!("null"))
+    )
+}
+
+/// Two `"type": "string"` schemas that differ (equal schemas short-circuit
+/// earlier) are combined into one string schema rather than wrapped in
+/// `oneOf`: keywords from either side (such as `"nullable"`) are kept, and
+/// a `"format"` the two sides disagree on is dropped, since a format is
+/// only sound if every observed sample matched it.
+fn merge_strings(schema1: &Schema, schema2: &Schema) -> Option<Schema> {
+    let (Value::Object(obj1), Value::Object(obj2)) =
+        (schema1.as_value(), schema2.as_value())
+    else {
+        return None;
+    };
+    if obj1.get("type") != Some(&json!("string"))
+        || obj2.get("type") != Some(&json!("string"))
+    {
+        return None;
+    }
+
+    let mut merged = obj1.clone();
+    for (key, value) in obj2 {
+        merged.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    if obj1.get("format") != obj2.get("format") {
+        merged.remove("format");
+    }
+    Schema::try_from(Value::Object(merged)).ok()
+}
+
+type FormatCheck = (&'static str, fn(&str) -> bool);
+
+/// Tests `value` against a cheap, ordered set of format checks and
+/// returns the first one that fully matches.
+fn infer_string_format(value: &str) -> Option<&'static str> {
+    const CHECKS: &[FormatCheck] = &[
+        ("date-time", is_rfc3339_date_time),
+        ("date", is_rfc3339_date),
+        ("time", is_rfc3339_time),
+        ("email", is_email),
+        ("uri", is_uri),
+        ("uuid", is_uuid),
+        ("ipv4", is_ipv4),
+        ("ipv6", is_ipv6),
+    ];
+    CHECKS
+        .iter()
+        .find(|(_, check)| check(value))
+        .map(|(name, _)| *name)
+}
+
+fn is_rfc3339_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && is_ascii_digits(&value[0..4])
+        && is_ascii_digits(&value[5..7])
+        && is_ascii_digits(&value[8..10])
+}
+
+fn is_rfc3339_time(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() < 8
+        || bytes[2] != b':'
+        || bytes[5] != b':'
+        || !is_ascii_digits(&value[0..2])
+        || !is_ascii_digits(&value[3..5])
+        || !is_ascii_digits(&value[6..8])
+    {
+        return false;
+    }
+
+    let rest = &value[8..];
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let digits_end = fraction
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(fraction.len());
+            if digits_end == 0 {
+                return false;
+            }
+            &fraction[digits_end..]
+        }
+        None => rest,
+    };
+
+    rest == "Z"
+        || rest == "z"
+        || ((rest.starts_with('+') || rest.starts_with('-'))
+            && rest.len() == 6
+            && rest.as_bytes()[3] == b':'
+            && is_ascii_digits(&rest[1..3])
+            && is_ascii_digits(&rest[4..6]))
+}
+
+fn is_rfc3339_date_time(value: &str) -> bool {
+    let Some((date, rest)) = value.split_once(['T', 't']) else {
+        return false;
+    };
+    is_rfc3339_date(date) && is_rfc3339_time(rest)
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.is_empty()
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && domain.contains('.')
+        && !value.contains(char::is_whitespace)
+        && value.matches('@').count() == 1
+}
+
+fn is_uri(value: &str) -> bool {
+    let Some(colon) = value.find(':') else {
+        return false;
+    };
+    let scheme = &value[..colon];
+    !scheme.is_empty()
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && colon + 1 < value.len()
+        && !value.contains(char::is_whitespace)
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    let expected_lengths = [8, 4, 4, 4, 12];
+    groups.len() == expected_lengths.len()
+        && groups
+            .iter()
+            .zip(expected_lengths)
+            .all(|(group, len)| {
+                group.len() == len
+                    && group.chars().all(|c| c.is_ascii_hexdigit())
+            })
+}
+
+fn is_ipv4(value: &str) -> bool {
+    let octets: Vec<&str> = value.split('.').collect();
+    octets.len() == 4
+        && octets.iter().all(|octet| {
+            !octet.is_empty()
+                && octet.len() <= 3
+                && octet.chars().all(|c| c.is_ascii_digit())
+                && octet.parse::<u16>().is_ok_and(|n| n <= 255)
+                && (*octet == "0" || !octet.starts_with('0'))
+        })
+}
+
+fn is_ipv6(value: &str) -> bool {
+    if value.matches("::").count() > 1 {
+        return false;
+    }
+    let has_shorthand = value.contains("::");
+    let groups: Vec<&str> =
+        value.split(':').filter(|group| !group.is_empty()).collect();
+    let group_count_ok = if has_shorthand {
+        groups.len() <= 7
+    } else {
+        groups.len() == 8
+    };
+
+    group_count_ok
+        && !groups.is_empty()
+        && groups.iter().all(|group| {
+            !group.is_empty()
+                && group.len() <= 4
+                && group.chars().all(|c| c.is_ascii_hexdigit())
+        })
+}
+
+fn is_ascii_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// An array "looks like" a fixed-shape tuple when it's short and its
+/// per-index schemas aren't all the same — a homogeneous array (e.g. a
+/// list of strings) should still collapse to a single `items` schema.
+const MAX_TUPLE_LEN: usize = 16;
+
+fn looks_like_tuple(item_schemas: &[Schema]) -> bool {
+    item_schemas.len() <= MAX_TUPLE_LEN
+        && item_schemas.windows(2).any(|pair| schemas_differ_in_kind(&pair[0], &pair[1]))
+}
+
+/// Whether two per-position schemas are different enough to suggest a
+/// positional tuple rather than a homogeneous (or record-like) list. Only
+/// a differing `type` counts as a genuine positional mismatch — two object
+/// schemas that merely differ in `properties`/`required` (e.g. two user
+/// records where one is missing an optional field) don't count, since
+/// that's the ordinary shape of a heterogeneous list-of-records, not a
+/// tuple; likewise two non-object schemas of the same `type` that differ
+/// in some other keyword (e.g. `infer_string_formats` attaching a
+/// `"format"` to one string but not another) are still homogeneous, not
+/// positional.
+fn schemas_differ_in_kind(a: &Schema, b: &Schema) -> bool {
+    let (Some(obj_a), Some(obj_b)) = (a.as_value().as_object(), b.as_value().as_object())
+    else {
+        return a != b;
+    };
+    obj_a.get("type") != obj_b.get("type")
+}
+
+/// Walks every object subschema reachable from `value` (through
+/// `properties`, `items`, `prefixItems` and `oneOf`) and tallies how many
+/// times each structurally distinct shape occurs, along with the
+/// property key that first introduced it.
+fn collect_object_shapes(
+    value: &Value,
+    key_hint: &str,
+    shapes: &mut HashMap<Value, (String, usize)>,
+) {
+    let Value::Object(obj) = value else { return };
+
+    if obj.get("type") == Some(&json!("object"))
+        && obj.contains_key("properties")
+    {
+        shapes
+            .entry(value.clone())
+            .or_insert_with(|| (key_hint.to_string(), 0))
+            .1 += 1;
+    }
+
+    if let Some(properties) = obj.get("properties").and_then(Value::as_object)
+    {
+        for (key, sub_value) in properties {
+            collect_object_shapes(sub_value, key, shapes);
+        }
+    }
+    if let Some(items) = obj.get("items") {
+        match items {
+            Value::Object(_) => {
+                collect_object_shapes(items, key_hint, shapes)
+            }
+            Value::Array(elements) => {
+                for element in elements {
+                    collect_object_shapes(element, key_hint, shapes);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(prefix_items) = obj.get("prefixItems").and_then(Value::as_array)
+    {
+        for element in prefix_items {
+            collect_object_shapes(element, key_hint, shapes);
+        }
+    }
+    if let Some(branches) = obj.get("oneOf").and_then(Value::as_array) {
+        for branch in branches {
+            collect_object_shapes(branch, key_hint, shapes);
+        }
+    }
+}
+
+/// Replaces `value` with `{"$ref": ...}` if it exactly matches a shape in
+/// `assigned`, otherwise recurses into its children looking for matches
+/// further down.
+fn replace_with_refs(
+    value: &mut Value,
+    assigned: &HashMap<Value, String>,
+    definitions_path: &str,
+) {
+    if let Some(name) = assigned.get(value) {
+        *value = json!({"$ref": format!("{definitions_path}{name}")});
+        return;
+    }
+
+    let Value::Object(obj) = value else { return };
+
+    if let Some(properties) =
+        obj.get_mut("properties").and_then(Value::as_object_mut)
+    {
+        for sub_value in properties.values_mut() {
+            replace_with_refs(sub_value, assigned, definitions_path);
+        }
+    }
+    if let Some(items) = obj.get_mut("items") {
+        match items {
+            Value::Object(_) => {
+                replace_with_refs(items, assigned, definitions_path)
+            }
+            Value::Array(elements) => {
+                for element in elements {
+                    replace_with_refs(element, assigned, definitions_path);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(prefix_items) =
+        obj.get_mut("prefixItems").and_then(Value::as_array_mut)
+    {
+        for element in prefix_items {
+            replace_with_refs(element, assigned, definitions_path);
+        }
+    }
+    if let Some(branches) = obj.get_mut("oneOf").and_then(Value::as_array_mut)
+    {
+        for branch in branches {
+            replace_with_refs(branch, assigned, definitions_path);
+        }
+    }
+}
+
+/// Inserts `defs` as a map at the location `definitions_path` points to
+/// (e.g. `#/definitions/` -> top-level `definitions`, or
+/// `#/components/schemas/` -> nested `components.schemas`), merging with
+/// whatever is already there.
+fn insert_definitions(
+    root: &mut Map<String, Value>,
+    definitions_path: &str,
+    defs: Vec<(String, Value)>,
+) {
+    let segments: Vec<&str> = definitions_path
+        .trim_start_matches('#')
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let Some((last, parents)) = segments.split_last() else { return };
+
+    let mut current = root;
+    for segment in parents {
+        current = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("definitions path segment is not an object");
+    }
+
+    let new_defs: Map<String, Value> = defs.into_iter().collect();
+    match current.get_mut(*last).and_then(Value::as_object_mut) {
+        Some(existing) => existing.extend(new_defs),
+        None => {
+            current.insert(last.to_string(), Value::Object(new_defs));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -190,7 +830,14 @@ mod tests {
     fn create_generator_with_mock(
         mock: &MockKnownTypes,
     ) -> SchemaGenerator<'_> {
-        SchemaGenerator { known_types: mock }
+        SchemaGenerator::new(SchemaSettings::draft07(), mock)
+    }
+
+    fn create_generator_with_settings<'a>(
+        settings: SchemaSettings,
+        mock: &'a MockKnownTypes,
+    ) -> SchemaGenerator<'a> {
+        SchemaGenerator::new(settings, mock)
     }
 
     #[test]
@@ -369,6 +1016,357 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_merge_schemas_nullable_as_keyword() {
+        let mock_types = create_test_generator();
+        let generator =
+            create_generator_with_settings(SchemaSettings::openapi3(), &mock_types);
+        let schema1 = json_schema!({"type": "string"});
+        let schema2 = json_schema!({"type": "null"});
+        let result = generator.merge_schemas(&schema1, &schema2).unwrap();
+        let expected = json_schema!({"type": "string", "nullable": true});
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_merge_schemas_option_add_null_type() {
+        let mock_types = create_test_generator();
+        let generator = create_generator_with_mock(&mock_types);
+        let schema1 = json_schema!({"type": "null"});
+        let schema2 = json_schema!({"type": "string"});
+        let result = generator.merge_schemas(&schema1, &schema2).unwrap();
+        let expected = json_schema!({"type": ["string", "null"]});
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_merge_schemas_option_add_null_type_flattens_repeated_merges() {
+        let mock_types = create_test_generator();
+        let generator = create_generator_with_mock(&mock_types);
+        let input = json!(["a", null, null]);
+        let result = generator.generate_schema(&input).unwrap();
+        let result = result.as_value();
+        assert_eq!(result["items"]["type"], json!(["string", "null"]));
+    }
+
+    #[test]
+    fn test_generate_object_schema_openapi3_omits_schema_keyword() {
+        let mock_types = create_test_generator();
+        let generator =
+            create_generator_with_settings(SchemaSettings::openapi3(), &mock_types);
+        let input = json!({"name": "John Doe"});
+        let result = generator.generate_schema(&input).unwrap();
+        let expected_json = json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        let expected = Schema::try_from(expected_json).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_generate_array_schema_tuple() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!(["2024-01-01", 42, true]);
+        let result = generator.generate_schema(&input).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "prefixItems": [
+                {"type": "string"},
+                {"type": "integer"},
+                {"type": "boolean"}
+            ],
+            "items": false
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_generate_array_schema_tuple_draft07() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!(["2024-01-01", 42]);
+        let result = generator.generate_array_schema(input.as_array().unwrap()).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "items": [
+                {"type": "string"},
+                {"type": "integer"}
+            ],
+            "additionalItems": false
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_generate_array_schema_homogeneous_not_treated_as_tuple() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!([1, 2, 3]);
+        let result = generator.generate_schema(&input).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "items": {"type": "integer"}
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_generate_array_schema_heterogeneous_records_not_treated_as_tuple() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!([
+            {"name": "Alice", "age": 30},
+            {"name": "Bob"}
+        ]);
+        let result = generator.generate_schema(&input).unwrap();
+        let result = result.as_value();
+        assert_eq!(result["type"], json!("array"));
+        assert!(result.get("prefixItems").is_none());
+        assert!(result["items"]["properties"].get("name").is_some());
+    }
+
+    #[test]
+    fn test_generate_array_schema_homogeneous_strings_with_formats_not_treated_as_tuple() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        settings.infer_string_formats = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!([
+            "2024-01-01",
+            "hello world",
+            "another plain string"
+        ]);
+        let result = generator.generate_schema(&input).unwrap();
+        let result = result.as_value();
+        assert_eq!(result["type"], json!("array"));
+        assert!(result.get("prefixItems").is_none());
+        assert_eq!(result["items"]["type"], json!("string"));
+    }
+
+    #[test]
+    fn test_merge_tuple_arrays_same_length() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let schema1 = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": false
+        });
+        let schema2 = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}, {"type": "boolean"}],
+            "items": false
+        });
+        let result = generator.merge_schemas(&schema1, &schema2).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "prefixItems": [
+                {"type": "string"},
+                {"oneOf": [{"type": "integer"}, {"type": "boolean"}]}
+            ],
+            "items": false
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_merge_tuple_arrays_length_mismatch_falls_back_to_common_schema() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft2020_12();
+        settings.tuple_arrays = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let schema1 = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": false
+        });
+        let schema2 = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}],
+            "items": false
+        });
+        let result = generator.merge_schemas(&schema1, &schema2).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "items": {
+                "oneOf": [
+                    {"oneOf": [{"type": "string"}, {"type": "integer"}]},
+                    {"type": "string"}
+                ]
+            }
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_dedupe_subschemas_noop_by_default() {
+        let mock_types = create_test_generator();
+        let generator = create_generator_with_mock(&mock_types);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "home": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}},
+                    "required": ["city"]
+                }
+            },
+            "required": ["home"]
+        });
+        let result = generator.dedupe_subschemas(schema.clone()).unwrap();
+        assert_eq!(result, schema);
+    }
+
+    #[test]
+    fn test_dedupe_subschemas_extracts_repeated_shape() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.inline_subschemas = false;
+        let generator = create_generator_with_settings(settings, &mock_types);
+
+        let address = json!({
+            "type": "object",
+            "properties": {"city": {"type": "string"}},
+            "required": ["city"]
+        });
+        let input = json!({
+            "type": "object",
+            "properties": {
+                "home": address.clone(),
+                "work": address,
+            },
+            "required": ["home", "work"]
+        });
+        let schema = Schema::try_from(input).unwrap();
+        let result = generator.dedupe_subschemas(schema).unwrap();
+        let result = result.as_value();
+
+        assert_eq!(
+            result["properties"]["home"],
+            json!({"$ref": "#/definitions/Home"})
+        );
+        assert_eq!(
+            result["properties"]["work"],
+            json!({"$ref": "#/definitions/Home"})
+        );
+        assert_eq!(
+            result["definitions"]["Home"],
+            json!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}},
+                "required": ["city"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_dedupe_subschemas_unique_shapes_stay_inline() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.inline_subschemas = false;
+        let generator = create_generator_with_settings(settings, &mock_types);
+
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "home": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}},
+                    "required": ["city"]
+                }
+            },
+            "required": ["home"]
+        });
+        let result = generator.dedupe_subschemas(schema.clone()).unwrap();
+        assert_eq!(result, schema);
+    }
+
+    #[test]
+    fn test_infer_string_format_date_time() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.infer_string_formats = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let result = generator
+            .generate_schema(&json!("2024-01-01T12:30:00Z"))
+            .unwrap();
+        let expected =
+            json_schema!({"type": "string", "format": "date-time"});
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_infer_string_format_date_email_uuid_ipv4() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.infer_string_formats = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+
+        let cases = [
+            ("2024-01-01", "date"),
+            ("user@example.com", "email"),
+            ("550e8400-e29b-41d4-a716-446655440000", "uuid"),
+            ("192.168.0.1", "ipv4"),
+            ("https://example.com/path", "uri"),
+        ];
+        for (value, format) in cases {
+            let result =
+                generator.generate_schema(&json!(value)).unwrap();
+            let expected = json_schema!({"type": "string", "format": format});
+            assert_eq!(result, expected, "value: {value}");
+        }
+    }
+
+    #[test]
+    fn test_infer_string_format_disabled_by_default() {
+        let mock_types = create_test_generator();
+        let generator = create_generator_with_mock(&mock_types);
+        let result = generator
+            .generate_schema(&json!("2024-01-01T12:30:00Z"))
+            .unwrap();
+        assert_eq!(result, json_schema!({"type": "string"}));
+    }
+
+    #[test]
+    fn test_infer_string_format_no_match_is_plain_string() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.infer_string_formats = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let result =
+            generator.generate_schema(&json!("just some text")).unwrap();
+        assert_eq!(result, json_schema!({"type": "string"}));
+    }
+
+    #[test]
+    fn test_merge_schemas_drops_format_when_samples_disagree() {
+        let mock_types = create_test_generator();
+        let mut settings = SchemaSettings::draft07();
+        settings.infer_string_formats = true;
+        let generator = create_generator_with_settings(settings, &mock_types);
+        let input = json!(["user@example.com", "not-an-email"]);
+        let result = generator.generate_schema(&input).unwrap();
+        let expected = json_schema!({
+            "type": "array",
+            "items": {"type": "string"}
+        });
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_generate_schema_with_ref() {
         let mock_types = create_test_generator();
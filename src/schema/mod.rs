@@ -0,0 +1,10 @@
+pub mod avro;
+pub mod generator;
+mod naming;
+pub mod settings;
+pub mod validator;
+
+pub use avro::AvroExporter;
+pub use generator::SchemaGenerator;
+pub use settings::SchemaSettings;
+pub use validator::{ParameterError, Validator};
@@ -0,0 +1,38 @@
+use std::collections::HashSet;
+
+/// Hands out identifiers derived from a hint string (e.g. the property key
+/// that first introduced a shape), capitalizing the first character and
+/// deduplicating collisions with a numeric suffix. Shared by
+/// [`super::avro`]'s Avro record names and [`super::generator`]'s
+/// `$defs`/`definitions` names.
+#[derive(Default)]
+pub(super) struct NameAllocator {
+    used: HashSet<String>,
+}
+
+impl NameAllocator {
+    /// `fallback` is used in place of `hint` when `hint` is empty.
+    pub(super) fn allocate(&mut self, hint: &str, fallback: &str) -> String {
+        let base = capitalize(hint, fallback);
+        if self.used.insert(base.clone()) {
+            return base;
+        }
+
+        let mut counter = 2;
+        loop {
+            let candidate = format!("{base}{counter}");
+            if self.used.insert(candidate.clone()) {
+                return candidate;
+            }
+            counter += 1;
+        }
+    }
+}
+
+fn capitalize(hint: &str, fallback: &str) -> String {
+    let mut chars = hint.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => fallback.to_string(),
+    }
+}
@@ -0,0 +1,103 @@
+/// Controls how [`super::SchemaGenerator`] renders the schemas it infers.
+///
+/// Mirrors the shape of schemars' `SchemaSettings`: rather than hardcoding a
+/// single JSON Schema dialect, the generator reads these knobs so the same
+/// inference logic can target a plain JSON Schema validator or an OpenAPI 3
+/// document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaSettings {
+    /// Value written to the top-level `$schema` keyword, or `None` to omit
+    /// it entirely (OpenAPI 3 documents don't carry a `$schema`).
+    pub meta_schema: Option<String>,
+
+    /// When a value is sometimes absent/null, represent it as
+    /// `{"type": [T, "null"]}` instead of wrapping it in `oneOf`.
+    pub option_add_null_type: bool,
+
+    /// Represent nullability with the OpenAPI `"nullable": true` keyword
+    /// instead of a JSON Schema null type/oneOf branch.
+    pub nullable_as_keyword: bool,
+
+    /// Base path used when emitting `$ref`s into a definitions map, e.g.
+    /// `"#/definitions/"` or `"#/components/schemas/"`.
+    pub definitions_path: String,
+
+    /// When an array's elements look positional (small fixed length,
+    /// heterogeneous per-index schemas), emit `prefixItems`/`items` tuple
+    /// form instead of collapsing every element into one common `items`
+    /// schema.
+    pub tuple_arrays: bool,
+
+    /// Unlike the other flags on this struct, this one does not affect
+    /// [`super::SchemaGenerator::generate_schema`] directly — deduplication
+    /// needs the whole generated document to find repeated shapes, so it
+    /// runs as a separate pass you call explicitly. When `false`, pass the
+    /// schema `generate_schema` returns into
+    /// [`super::SchemaGenerator::dedupe_subschemas`] to hoist object shapes
+    /// that occur more than once into a shared definitions map and replace
+    /// the inlined duplicates with `$ref`s. `true` (the default) makes
+    /// `dedupe_subschemas` a no-op, leaving every subschema inlined.
+    pub inline_subschemas: bool,
+
+    /// When `true`, string instances are tested against a cheap set of
+    /// format checks (RFC 3339 date-time/date/time, email, uri, uuid,
+    /// ipv4/ipv6) and the first full match is attached as a `"format"`
+    /// keyword. `false` (the default) emits plain `{"type": "string"}`
+    /// schemas with no `"format"`.
+    pub infer_string_formats: bool,
+}
+
+impl SchemaSettings {
+    /// Settings for plain JSON Schema draft-07, the default. A value seen
+    /// both as `null` and as another type is merged into a
+    /// `{"type": [T, "null"]}` array rather than a `oneOf`.
+    pub fn draft07() -> Self {
+        Self {
+            meta_schema: Some(
+                "http://json-schema.org/draft-07/schema#".to_string(),
+            ),
+            option_add_null_type: true,
+            nullable_as_keyword: false,
+            definitions_path: "#/definitions/".to_string(),
+            tuple_arrays: false,
+            inline_subschemas: true,
+            infer_string_formats: false,
+        }
+    }
+
+    /// Settings for the 2020-12 JSON Schema dialect.
+    pub fn draft2020_12() -> Self {
+        Self {
+            meta_schema: Some(
+                "https://json-schema.org/draft/2020-12/schema".to_string(),
+            ),
+            option_add_null_type: true,
+            nullable_as_keyword: false,
+            definitions_path: "#/$defs/".to_string(),
+            tuple_arrays: false,
+            inline_subschemas: true,
+            infer_string_formats: false,
+        }
+    }
+
+    /// Settings tuned for embedding inferred shapes in an OpenAPI 3
+    /// document: no `$schema`, nulls folded into `"nullable": true`, and
+    /// refs pointing into `#/components/schemas/`.
+    pub fn openapi3() -> Self {
+        Self {
+            meta_schema: None,
+            option_add_null_type: false,
+            nullable_as_keyword: true,
+            definitions_path: "#/components/schemas/".to_string(),
+            tuple_arrays: false,
+            inline_subschemas: true,
+            infer_string_formats: false,
+        }
+    }
+}
+
+impl Default for SchemaSettings {
+    fn default() -> Self {
+        Self::draft07()
+    }
+}
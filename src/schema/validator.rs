@@ -0,0 +1,571 @@
+use schemars::Schema;
+use serde_json::{Map, Value};
+
+use super::generator::TryGet;
+
+/// Every validation failure found while walking an instance against a
+/// schema, each paired with the JSON pointer of the value that failed.
+///
+/// Mirrors Proxmox's `ParameterError`: callers can report every bad field
+/// in a form at once instead of bailing out on the first problem.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParameterError {
+    errors: Vec<(String, String)>,
+}
+
+impl ParameterError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.errors.push((path.into(), message.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn errors(&self) -> &[(String, String)] {
+        &self.errors
+    }
+
+    fn extend(&mut self, other: ParameterError) {
+        self.errors.extend(other.errors);
+    }
+}
+
+/// Validates a [`serde_json::Value`] against a [`Schema`], supporting the
+/// keywords [`super::SchemaGenerator`] actually produces: `type`,
+/// `nullable`, `properties`/`required`, `items`/`prefixItems` (and
+/// `additionalItems`), `oneOf`, and `$ref` (resolved through
+/// `definitions`). `format` is not checked.
+pub struct Validator<'defs> {
+    definitions: &'defs dyn TryGet<String, Schema>,
+}
+
+impl<'defs> Validator<'defs> {
+    pub fn new(definitions: &'defs dyn TryGet<String, Schema>) -> Self {
+        Self { definitions }
+    }
+
+    /// Validates `instance` against `schema`, collecting every failure
+    /// rather than stopping at the first.
+    pub fn validate(
+        &self,
+        schema: &Schema,
+        instance: &Value,
+    ) -> Result<(), ParameterError> {
+        let mut errors = ParameterError::new();
+        self.validate_at(schema, instance, "", &mut errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    fn validate_at(
+        &self,
+        schema: &Schema,
+        instance: &Value,
+        path: &str,
+        errors: &mut ParameterError,
+    ) {
+        let Value::Object(obj) = schema.as_value() else {
+            return;
+        };
+
+        if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+            match self.resolve_ref(reference) {
+                Some(resolved) => {
+                    self.validate_at(&resolved, instance, path, errors)
+                }
+                None => errors
+                    .push(path, format!("unresolved $ref '{reference}'")),
+            }
+            return;
+        }
+
+        if obj.get("nullable") == Some(&Value::Bool(true)) && instance.is_null()
+        {
+            return;
+        }
+
+        if let Some(branches) = obj.get("oneOf").and_then(Value::as_array) {
+            self.validate_one_of(branches, instance, path, errors);
+            return;
+        }
+
+        if let Some(ty) = obj.get("type")
+            && !matches_any_type(ty, instance)
+        {
+            errors.push(
+                path,
+                format!(
+                    "expected type '{}', got '{}'",
+                    format_type(ty),
+                    type_name(instance)
+                ),
+            );
+            return;
+        }
+
+        match instance {
+            Value::Object(instance_obj) => {
+                self.validate_object(obj, instance_obj, path, errors)
+            }
+            Value::Array(instance_arr) => {
+                self.validate_array(obj, instance_arr, path, errors)
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_one_of(
+        &self,
+        branches: &[Value],
+        instance: &Value,
+        path: &str,
+        errors: &mut ParameterError,
+    ) {
+        let mut matched = 0;
+        let mut branch_errors = Vec::new();
+
+        for branch in branches {
+            let Ok(branch_schema) = Schema::try_from(branch.clone()) else {
+                continue;
+            };
+            let mut local = ParameterError::new();
+            self.validate_at(&branch_schema, instance, path, &mut local);
+            if local.is_empty() {
+                matched += 1;
+            } else {
+                branch_errors.push(local);
+            }
+        }
+
+        if matched != 1 {
+            errors.push(
+                path,
+                format!(
+                    "expected exactly one oneOf branch to match, {matched} did"
+                ),
+            );
+            for branch in branch_errors {
+                errors.extend(branch);
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema_obj: &Map<String, Value>,
+        instance_obj: &Map<String, Value>,
+        path: &str,
+        errors: &mut ParameterError,
+    ) {
+        if let Some(required) =
+            schema_obj.get("required").and_then(Value::as_array)
+        {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !instance_obj.contains_key(key) {
+                    errors.push(
+                        child_path(path, key),
+                        "required property is missing",
+                    );
+                }
+            }
+        }
+
+        let Some(properties) =
+            schema_obj.get("properties").and_then(Value::as_object)
+        else {
+            return;
+        };
+        for (key, sub_schema) in properties {
+            let Some(value) = instance_obj.get(key) else {
+                continue;
+            };
+            if let Ok(sub_schema) = Schema::try_from(sub_schema.clone()) {
+                self.validate_at(
+                    &sub_schema,
+                    value,
+                    &child_path(path, key),
+                    errors,
+                );
+            }
+        }
+    }
+
+    fn validate_array(
+        &self,
+        schema_obj: &Map<String, Value>,
+        instance_arr: &[Value],
+        path: &str,
+        errors: &mut ParameterError,
+    ) {
+        if let Some(prefix_items) =
+            schema_obj.get("prefixItems").and_then(Value::as_array)
+        {
+            for (index, item_schema) in prefix_items.iter().enumerate() {
+                let Some(value) = instance_arr.get(index) else {
+                    continue;
+                };
+                if let Ok(item_schema) = Schema::try_from(item_schema.clone())
+                {
+                    self.validate_at(
+                        &item_schema,
+                        value,
+                        &index_path(path, index),
+                        errors,
+                    );
+                }
+            }
+            if schema_obj.get("items") == Some(&Value::Bool(false))
+                && instance_arr.len() > prefix_items.len()
+            {
+                errors.push(
+                    path,
+                    format!(
+                        "array has {} element(s), expected at most {}",
+                        instance_arr.len(),
+                        prefix_items.len()
+                    ),
+                );
+            }
+            return;
+        }
+
+        let Some(items) = schema_obj.get("items") else {
+            return;
+        };
+
+        // Draft-07 tuple form: `"items"` is an array of per-position
+        // schemas rather than a single schema applied to every element.
+        if let Some(item_schemas) = items.as_array() {
+            for (index, item_schema) in item_schemas.iter().enumerate() {
+                let Some(value) = instance_arr.get(index) else {
+                    continue;
+                };
+                if let Ok(item_schema) = Schema::try_from(item_schema.clone())
+                {
+                    self.validate_at(
+                        &item_schema,
+                        value,
+                        &index_path(path, index),
+                        errors,
+                    );
+                }
+            }
+            if schema_obj.get("additionalItems") == Some(&Value::Bool(false))
+                && instance_arr.len() > item_schemas.len()
+            {
+                errors.push(
+                    path,
+                    format!(
+                        "array has {} element(s), expected at most {}",
+                        instance_arr.len(),
+                        item_schemas.len()
+                    ),
+                );
+            }
+            return;
+        }
+
+        let Ok(item_schema) = Schema::try_from(items.clone()) else {
+            return;
+        };
+        for (index, value) in instance_arr.iter().enumerate() {
+            self.validate_at(
+                &item_schema,
+                value,
+                &index_path(path, index),
+                errors,
+            );
+        }
+    }
+
+    fn resolve_ref(&self, reference: &str) -> Option<Schema> {
+        self.definitions.try_get(&reference.to_string())
+    }
+}
+
+/// `type` may be a single string or (the generator's representation of a
+/// nullable value, via `SchemaSettings::option_add_null_type`) an array of
+/// strings, any one of which a matching instance may satisfy.
+fn matches_any_type(ty: &Value, instance: &Value) -> bool {
+    match ty {
+        Value::String(ty) => matches_type(ty, instance),
+        Value::Array(types) => types.iter().any(|ty| match ty.as_str() {
+            Some(ty) => matches_type(ty, instance),
+            None => true,
+        }),
+        _ => true,
+    }
+}
+
+fn matches_type(ty: &str, instance: &Value) -> bool {
+    match ty {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        "number" => instance.is_number(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        _ => true,
+    }
+}
+
+fn format_type(ty: &Value) -> String {
+    match ty {
+        Value::String(ty) => ty.clone(),
+        Value::Array(types) => types
+            .iter()
+            .map(|ty| ty.as_str().map(str::to_string).unwrap_or_else(|| ty.to_string()))
+            .collect::<Vec<_>>()
+            .join("|"),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    format!("{parent}/{key}")
+}
+
+fn index_path(parent: &str, index: usize) -> String {
+    format!("{parent}/{index}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::json_schema;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    struct MockDefinitions {
+        refs: HashMap<String, Schema>,
+    }
+
+    impl TryGet<String, Schema> for MockDefinitions {
+        fn try_get(&self, key: &String) -> Option<Schema> {
+            self.refs.get(key).cloned()
+        }
+    }
+
+    fn no_refs() -> MockDefinitions {
+        MockDefinitions { refs: HashMap::new() }
+    }
+
+    #[test]
+    fn test_validate_simple_type_ok() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"type": "string"});
+        assert!(validator.validate(&schema, &json!("hello")).is_ok());
+    }
+
+    #[test]
+    fn test_validate_simple_type_mismatch() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"type": "string"});
+        let errors = validator.validate(&schema, &json!(42)).unwrap_err();
+        assert_eq!(
+            errors.errors(),
+            &[("".to_string(), "expected type 'string', got 'number'".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_validate_nullable_array_type_accepts_either_member() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"type": ["string", "null"]});
+        assert!(validator.validate(&schema, &json!("hello")).is_ok());
+        assert!(validator.validate(&schema, &json!(null)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_nullable_array_type_rejects_other_types() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"type": ["string", "null"]});
+        let errors = validator.validate(&schema, &json!(true)).unwrap_err();
+        assert_eq!(
+            errors.errors(),
+            &[(
+                "".to_string(),
+                "expected type 'string|null', got 'boolean'".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_object_missing_required() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        let errors =
+            validator.validate(&schema, &json!({})).unwrap_err();
+        assert_eq!(
+            errors.errors(),
+            &[("/name".to_string(), "required property is missing".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_validate_object_collects_every_field_error() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
+            "required": ["name", "age"]
+        });
+        let errors = validator
+            .validate(&schema, &json!({"name": 1, "age": "old"}))
+            .unwrap_err();
+        assert_eq!(errors.errors().len(), 2);
+    }
+
+    #[test]
+    fn test_validate_array_items() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "array",
+            "items": {"type": "integer"}
+        });
+        let errors = validator
+            .validate(&schema, &json!([1, "two", 3]))
+            .unwrap_err();
+        assert_eq!(
+            errors.errors(),
+            &[(
+                "/1".to_string(),
+                "expected type 'integer', got 'string'".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_array_prefix_items() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": false
+        });
+        assert!(validator.validate(&schema, &json!(["a", 1])).is_ok());
+        let errors = validator
+            .validate(&schema, &json!(["a", "b"]))
+            .unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_array_prefix_items_rejects_extra_elements() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "array",
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": false
+        });
+        let errors = validator
+            .validate(&schema, &json!(["a", 1, "unexpected"]))
+            .unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_array_items_tuple_form() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "array",
+            "items": [{"type": "string"}, {"type": "integer"}],
+            "additionalItems": false
+        });
+        assert!(validator.validate(&schema, &json!(["a", 1])).is_ok());
+        let errors = validator
+            .validate(&schema, &json!(["a", "b"]))
+            .unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_array_items_tuple_form_rejects_extra_elements() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "type": "array",
+            "items": [{"type": "string"}, {"type": "integer"}],
+            "additionalItems": false
+        });
+        let errors = validator
+            .validate(&schema, &json!(["a", 1, "unexpected"]))
+            .unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_one_of_exactly_one_match() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({
+            "oneOf": [{"type": "string"}, {"type": "integer"}]
+        });
+        assert!(validator.validate(&schema, &json!("ok")).is_ok());
+        assert!(validator.validate(&schema, &json!(true)).is_err());
+    }
+
+    #[test]
+    fn test_validate_ref_resolves_through_definitions() {
+        let mut defs = no_refs();
+        defs.refs.insert(
+            "#/definitions/address".to_string(),
+            json_schema!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}},
+                "required": ["city"]
+            }),
+        );
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"$ref": "#/definitions/address"});
+        assert!(
+            validator
+                .validate(&schema, &json!({"city": "NYC"}))
+                .is_ok()
+        );
+        assert!(validator.validate(&schema, &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_validate_ref_unresolved() {
+        let defs = no_refs();
+        let validator = Validator::new(&defs);
+        let schema = json_schema!({"$ref": "#/definitions/missing"});
+        let errors =
+            validator.validate(&schema, &json!({})).unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+    }
+}
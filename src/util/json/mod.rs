@@ -1,16 +1,161 @@
-pub fn merge(a: &mut Value, b: &Value) {
+use serde_json::Value;
+
+/// How array values are combined when both `a` and `b` have an array at
+/// the same position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArrayMergeMode {
+    /// `b`'s array replaces `a`'s entirely. Matches [`merge`]'s behavior.
+    Override,
+    /// `b`'s elements are appended after `a`'s.
+    Concat,
+    /// Merge element-wise by index, recursing into matching positions;
+    /// elements beyond the shorter array are appended as-is.
+    ByIndex,
+}
+
+/// Selects how [`merge_with`] combines two JSON documents, in the style of
+/// the nimbus `Defaults` pattern.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MergeOptions {
+    pub arrays: ArrayMergeMode,
+
+    /// When `true`, a `null` in `b` deletes the matching key from `a`
+    /// instead of being skipped.
+    pub null_deletes: bool,
+
+    /// When `true`, a key present in `b` but missing from `a` is inserted
+    /// using `b`'s value as the default.
+    pub missing_keys_take_default: bool,
+}
+
+impl Default for MergeOptions {
+    /// [`merge`]'s existing semantics: arrays are overridden wholesale,
+    /// `null` in `b` is skipped rather than deleting anything, and missing
+    /// keys are filled in from `b`.
+    fn default() -> Self {
+        Self {
+            arrays: ArrayMergeMode::Override,
+            null_deletes: false,
+            missing_keys_take_default: true,
+        }
+    }
+}
+
+/// Deep-merges `b` into `a` in place, following `options`.
+pub fn merge_with(a: &mut Value, b: &Value, options: &MergeOptions) {
     match (a, b) {
         (&mut Value::Object(ref mut a), Value::Object(b)) => {
             for (k, v) in b {
-                // preventing null copy
-                if !v.is_null() {
-                    merge(a.entry(k.clone()).or_insert(Value::Null), v);
+                if v.is_null() {
+                    if options.null_deletes {
+                        a.remove(k);
+                    }
+                    continue;
+                }
+                match a.get_mut(k) {
+                    Some(existing) => merge_with(existing, v, options),
+                    None if options.missing_keys_take_default => {
+                        a.insert(k.clone(), v.clone());
+                    }
+                    None => {}
+                }
+            }
+        }
+        (&mut Value::Array(ref mut a), Value::Array(b))
+            if options.arrays != ArrayMergeMode::Override =>
+        {
+            match options.arrays {
+                ArrayMergeMode::Concat => a.extend(b.iter().cloned()),
+                ArrayMergeMode::ByIndex => {
+                    for (i, bv) in b.iter().enumerate() {
+                        match a.get_mut(i) {
+                            Some(av) => merge_with(av, bv, options),
+                            None => a.push(bv.clone()),
+                        }
+                    }
                 }
+                ArrayMergeMode::Override => unreachable!(),
             }
         }
-        // override if a and b is not a object
+        // override if a and b is not a object (or not a mergeable array)
         (a, b) => {
             *a = b.clone();
         }
     }
 }
+
+pub fn merge(a: &mut Value, b: &Value) {
+    merge_with(a, b, &MergeOptions::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_with_array_override() {
+        let mut a = json!({"tags": ["a", "b"]});
+        let b = json!({"tags": ["c"]});
+        merge_with(&mut a, &b, &MergeOptions::default());
+        assert_eq!(a, json!({"tags": ["c"]}));
+    }
+
+    #[test]
+    fn test_merge_with_array_concat() {
+        let mut a = json!({"tags": ["a", "b"]});
+        let b = json!({"tags": ["c"]});
+        let options = MergeOptions { arrays: ArrayMergeMode::Concat, ..MergeOptions::default() };
+        merge_with(&mut a, &b, &options);
+        assert_eq!(a, json!({"tags": ["a", "b", "c"]}));
+    }
+
+    #[test]
+    fn test_merge_with_array_by_index() {
+        let mut a = json!({"point": [{"x": 1, "y": 2}, "unchanged"]});
+        let b = json!({"point": [{"x": 9}, "replaced", "extra"]});
+        let options = MergeOptions { arrays: ArrayMergeMode::ByIndex, ..MergeOptions::default() };
+        merge_with(&mut a, &b, &options);
+        assert_eq!(
+            a,
+            json!({"point": [{"x": 9, "y": 2}, "replaced", "extra"]})
+        );
+    }
+
+    #[test]
+    fn test_merge_with_null_deletes() {
+        let mut a = json!({"name": "Alice", "age": 30});
+        let b = json!({"age": null});
+        let options = MergeOptions { null_deletes: true, ..MergeOptions::default() };
+        merge_with(&mut a, &b, &options);
+        assert_eq!(a, json!({"name": "Alice"}));
+    }
+
+    #[test]
+    fn test_merge_with_null_deletes_false_keeps_existing_value() {
+        let mut a = json!({"name": "Alice", "age": 30});
+        let b = json!({"age": null});
+        merge_with(&mut a, &b, &MergeOptions::default());
+        assert_eq!(a, json!({"name": "Alice", "age": 30}));
+    }
+
+    #[test]
+    fn test_merge_with_missing_keys_take_default_false() {
+        let mut a = json!({"name": "Alice"});
+        let b = json!({"name": "Bob", "age": 30});
+        let options = MergeOptions {
+            missing_keys_take_default: false,
+            ..MergeOptions::default()
+        };
+        merge_with(&mut a, &b, &options);
+        assert_eq!(a, json!({"name": "Bob"}));
+    }
+
+    #[test]
+    fn test_merge_preserves_existing_semantics() {
+        let mut a = json!({"name": "Alice", "age": 30});
+        let b = json!({"age": null, "city": "NYC"});
+        merge(&mut a, &b);
+        assert_eq!(a, json!({"name": "Alice", "age": 30, "city": "NYC"}));
+    }
+}